@@ -6,10 +6,13 @@ declare_id!("4YxE5GRA7UsNwLtpyQcL3F245F6te4Gg2BPAhMvWoKh5");
 pub mod solana_fee_predictor {
     use super::*;
 
-    /// Initialize fee data account
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    /// Initialize fee data account. `lambda` is the decay rate used to age out
+    /// old samples when computing statistics; pass `None` to use the default
+    /// ~6 hour half-life.
+    pub fn initialize(ctx: Context<Initialize>, lambda: Option<f64>) -> Result<()> {
         let fee_data = &mut ctx.accounts.fee_data;
         fee_data.authority = *ctx.accounts.payer.key;
+        fee_data.lambda = lambda.unwrap_or_else(default_lambda);
         fee_data.last_updated = Clock::get()?.unix_timestamp;
         msg!("Fee data account initialized");
         Ok(())
@@ -22,62 +25,259 @@ pub mod solana_fee_predictor {
         tps: u32,
         slot: u64,
         compute_units_consumed: u64,
+        compute_unit_price: u64,
     ) -> Result<()> {
         let fee_data = &mut ctx.accounts.fee_data;
-        
-        // Maintain rolling window of 144 samples (~24 hours if stored every 10 mins)
-        if fee_data.historical_data.len() >= 144 {
-            fee_data.historical_data.remove(0);
+        let now = Clock::get()?.unix_timestamp;
+
+        // A sample's fee band occurrence count is this sample plus every sample
+        // already in the window that landed in the same fee band, mirroring the
+        // validator cost model's notion of how "common" a fee level is.
+        let occurrence_count = fee_data
+            .historical_data
+            .iter()
+            .filter(|s| fee_band(s.fee) == fee_band(fee))
+            .count() as u32
+            + 1;
+
+        // Maintain a rolling window of MAX_HISTORICAL_SAMPLES samples (~24 hours if
+        // stored every 10 mins), evicting the sample that is both old and rare
+        // (lowest recency-decay + occurrence score) instead of always the oldest arrival.
+        if fee_data.historical_data.len() >= MAX_HISTORICAL_SAMPLES {
+            evict_lowest_scored(&mut fee_data.historical_data, fee_data.lambda, now);
         }
-        
+
         fee_data.historical_data.push(FeeSample {
             fee,
             tps,
             slot,
-            compute_units_consumed: compute_units_consumed,
-            timestamp: Clock::get()?.unix_timestamp,
+            compute_units_consumed,
+            compute_unit_price,
+            occurrence_count,
+            timestamp: now,
         });
-        
-        fee_data.last_updated = Clock::get()?.unix_timestamp;
+
+        fee_data.last_updated = now;
         msg!("Stored new fee data at slot {}", slot);
-        
+
         Ok(())
     }
 
-    /// Predict optimal fee based on historical data
+    /// Predict the compute unit price (micro-lamports per CU) to bid, and the
+    /// total fee it implies, based on historical data. The network prioritizes
+    /// transactions by compute unit price rather than total fee, so that's what
+    /// we estimate percentiles over.
     pub fn predict_fee(
         ctx: Context<PredictFee>,
         compute_units_estimate: u64,
         priority_level: PriorityLevel,
     ) -> Result<FeePrediction> {
         let fee_data = &ctx.accounts.fee_data;
-        
+
         if fee_data.historical_data.is_empty() {
             msg!("No historical data available - returning default prediction");
             return Ok(FeePrediction::default());
         }
-        
-        // Calculate statistics
-        let avg_fee = calculate_average(&fee_data.historical_data, |s| s.fee);
-        let avg_compute_units = calculate_average(&fee_data.historical_data, |s| s.compute_units_consumed);
-        
-        // Adjust for priority level
-        let priority_multiplier = priority_level.multiplier();
-        
-        // Scale fee based on compute units
-        let compute_scaling = if avg_compute_units > 0 {
-            compute_units_estimate as f64 / avg_compute_units as f64
-        } else {
-            1.0
-        };
-        
-        let estimated_fee = (avg_fee as f64 * priority_multiplier * compute_scaling) as u64;
-        
+
+        let now = Clock::get()?.unix_timestamp;
+
+        // Calculate statistics, decay-weighting each sample by its age so a
+        // 24-hour-old sample no longer counts the same as the latest one
+        let percentiles = calculate_percentiles(
+            &fee_data.historical_data,
+            |s| s.compute_unit_price,
+            fee_data.lambda,
+            now,
+        );
+
+        // Adjust for priority level using the percentile it maps to
+        let recommended_compute_unit_price = priority_level.base_fee(&percentiles);
+
+        // Tiered base compute fee: the first bin whose limit covers the estimate,
+        // falling back to the highest configured bin, or BASE_SIGNATURE_FEE if no
+        // bins have been configured yet.
+        let base_compute_fee = fee_data
+            .fee_bins
+            .iter()
+            .find(|bin| bin.limit >= compute_units_estimate)
+            .or_else(|| fee_data.fee_bins.last())
+            .map(|bin| bin.fee)
+            .unwrap_or(BASE_SIGNATURE_FEE);
+
+        let estimated_fee =
+            base_compute_fee + (recommended_compute_unit_price * compute_units_estimate) / 1_000_000;
+
         Ok(FeePrediction {
             estimated_fee,
+            recommended_compute_unit_price,
             last_updated: fee_data.last_updated,
-            confidence: calculate_confidence(&fee_data.historical_data),
+            confidence: calculate_confidence(&fee_data.historical_data, fee_data.lambda, now),
             priority_level,
+            compute_unit_price_percentiles: percentiles,
+        })
+    }
+
+    /// Set the tiered compute-fee-bin structure used as the base fee in `predict_fee`,
+    /// keyed by compute unit limit (mirrors the validator's `FeeStructure` tiers)
+    pub fn set_fee_bins(ctx: Context<SetFeeBins>, fee_bins: Vec<FeeBin>) -> Result<()> {
+        require!(
+            fee_bins.len() <= MAX_FEE_BINS,
+            ErrorCode::TooManyFeeBins
+        );
+
+        let fee_data = &mut ctx.accounts.fee_data;
+
+        let mut fee_bins = fee_bins;
+        fee_bins.sort_by_key(|bin| bin.limit);
+
+        fee_data.fee_bins = fee_bins;
+        fee_data.last_updated = Clock::get()?.unix_timestamp;
+        msg!("Set {} fee bin(s)", fee_data.fee_bins.len());
+
+        Ok(())
+    }
+
+    /// Initialize the account-keyed fee tracking account
+    pub fn initialize_account_fee_data(ctx: Context<InitializeAccountFeeData>) -> Result<()> {
+        let account_fee_data = &mut ctx.accounts.account_fee_data;
+        account_fee_data.authority = *ctx.accounts.payer.key;
+        account_fee_data.last_updated = Clock::get()?.unix_timestamp;
+        msg!("Account fee data account initialized");
+        Ok(())
+    }
+
+    /// Record the fee paid by a transaction against every writable account it locked
+    pub fn store_account_fee_data(
+        ctx: Context<StoreAccountFeeData>,
+        account_keys: Vec<Pubkey>,
+        fee: u64,
+        compute_units_consumed: u64,
+    ) -> Result<()> {
+        let account_fee_data = &mut ctx.accounts.account_fee_data;
+        let now = Clock::get()?.unix_timestamp;
+        let num_accounts = account_keys.len();
+
+        for account in account_keys {
+            if let Some(record) = account_fee_data
+                .accounts
+                .iter_mut()
+                .find(|r| r.account == account)
+            {
+                if record.fees.len() >= MAX_FEES_PER_ACCOUNT {
+                    record.fees.remove(0);
+                }
+                record.fees.push(fee);
+                record.compute_units_consumed = compute_units_consumed;
+                record.last_updated = now;
+            } else {
+                if account_fee_data.accounts.len() >= MAX_TRACKED_ACCOUNTS {
+                    evict_least_recently_updated(&mut account_fee_data.accounts);
+                }
+                account_fee_data.accounts.push(AccountFeeRecord {
+                    account,
+                    fees: vec![fee],
+                    compute_units_consumed,
+                    last_updated: now,
+                });
+            }
+        }
+
+        account_fee_data.last_updated = now;
+        msg!("Stored account fee data for {} account(s)", num_accounts);
+
+        Ok(())
+    }
+
+    /// Predict the fee needed to outbid the most-contended writable account locked
+    /// by the transaction, since a transaction must clear every account it writes to.
+    pub fn predict_fee_for_accounts(
+        ctx: Context<PredictFeeForAccounts>,
+        account_keys: Vec<Pubkey>,
+    ) -> Result<AccountFeePrediction> {
+        let account_fee_data = &ctx.accounts.account_fee_data;
+
+        let mut recommended_fee = 0u64;
+        let mut max_fee = 0u64;
+        let mut min_fee = u64::MAX;
+
+        for key in &account_keys {
+            let Some(record) = account_fee_data.accounts.iter().find(|r| r.account == *key) else {
+                continue;
+            };
+
+            let mut fees = record.fees.clone();
+            fees.sort_unstable();
+            if fees.is_empty() {
+                continue;
+            }
+
+            let median = fees[fees.len() / 2];
+            let max = fees[fees.len() - 1];
+            let min = fees[0];
+
+            recommended_fee = recommended_fee.max(median);
+            max_fee = max_fee.max(max);
+            min_fee = min_fee.min(min);
+        }
+
+        if min_fee == u64::MAX {
+            min_fee = 0;
+        }
+
+        Ok(AccountFeePrediction {
+            recommended_fee,
+            max_fee,
+            min_fee,
+        })
+    }
+
+    /// Read-only windowed fee history, modeled on Ethereum's `eth_feeHistory`:
+    /// returns the base fee for each of the last `block_count` samples plus,
+    /// for each caller-supplied percentile, the fee observed at that percentile
+    /// across the window.
+    pub fn get_fee_history(
+        ctx: Context<PredictFee>,
+        block_count: u32,
+        reward_percentiles: Vec<u8>,
+    ) -> Result<FeeHistory> {
+        let fee_data = &ctx.accounts.fee_data;
+
+        require!(
+            !fee_data.historical_data.is_empty(),
+            ErrorCode::InsufficientData
+        );
+        require!(
+            reward_percentiles.len() <= 100,
+            ErrorCode::InvalidPercentiles
+        );
+        require!(
+            reward_percentiles.iter().all(|&p| p <= 100),
+            ErrorCode::InvalidPercentiles
+        );
+        require!(
+            reward_percentiles.windows(2).all(|w| w[0] < w[1]),
+            ErrorCode::InvalidPercentiles
+        );
+
+        let block_count = (block_count.clamp(1, 144) as usize).min(fee_data.historical_data.len());
+        let window = &fee_data.historical_data[fee_data.historical_data.len() - block_count..];
+
+        let oldest_slot = window.first().map(|s| s.slot).unwrap_or(0);
+        let base_fees: Vec<u64> = window.iter().map(|s| s.fee).collect();
+
+        let mut sorted_fees = base_fees.clone();
+        sorted_fees.sort_unstable();
+        let len = sorted_fees.len();
+        let reward_row: Vec<u64> = reward_percentiles
+            .iter()
+            .map(|&p| sorted_fees[(len * p as usize / 100).min(len - 1)])
+            .collect();
+        let rewards = vec![reward_row; window.len()];
+
+        Ok(FeeHistory {
+            oldest_slot,
+            base_fees,
+            rewards,
         })
     }
 }
@@ -87,9 +287,24 @@ pub mod solana_fee_predictor {
 pub struct FeeData {
     pub authority: Pubkey,
     pub last_updated: i64,
+    /// Decay rate applied to sample age when weighting statistics in `predict_fee`
+    pub lambda: f64,
     pub historical_data: Vec<FeeSample>,
+    /// Tiered compute-fee bins, sorted ascending by `limit`
+    pub fee_bins: Vec<FeeBin>,
 }
 
+/// A tiered compute fee: transactions estimating at most `limit` compute units
+/// pay `fee` as their base compute fee
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct FeeBin {
+    pub limit: u64,
+    pub fee: u64,
+}
+
+/// Maximum number of compute-fee bins that can be configured
+pub const MAX_FEE_BINS: usize = 16;
+
 /// Individual fee sample
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct FeeSample {
@@ -97,9 +312,36 @@ pub struct FeeSample {
     pub tps: u32,
     pub slot: u64,
     pub compute_units_consumed: u64,
+    /// Compute unit price paid, in micro-lamports per CU
+    pub compute_unit_price: u64,
+    /// How many samples in the window shared this sample's fee band at the
+    /// time it was stored, counting this sample itself
+    pub occurrence_count: u32,
     pub timestamp: i64,
 }
 
+/// Base signature fee, in lamports, charged per transaction signature
+pub const BASE_SIGNATURE_FEE: u64 = 5_000;
+
+/// Maximum rolling-window samples retained in `historical_data` (~24 hours if stored every 10 mins)
+pub const MAX_HISTORICAL_SAMPLES: usize = 144;
+
+/// Width, in lamports, of the fee bands used to track occurrence counts
+pub const FEE_BAND_WIDTH: u64 = 1_000;
+
+/// Default half-life, in seconds, for the EWMA decay applied to historical samples
+pub const DEFAULT_LAMBDA_HALF_LIFE_SECONDS: f64 = 6.0 * 60.0 * 60.0;
+
+/// Default decay rate corresponding to `DEFAULT_LAMBDA_HALF_LIFE_SECONDS`
+fn default_lambda() -> f64 {
+    std::f64::consts::LN_2 / DEFAULT_LAMBDA_HALF_LIFE_SECONDS
+}
+
+/// Bucket a fee into a band for occurrence-count tracking
+fn fee_band(fee: u64) -> u64 {
+    fee / FEE_BAND_WIDTH
+}
+
 /// Priority level for transactions
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
 pub enum PriorityLevel {
@@ -109,11 +351,14 @@ pub enum PriorityLevel {
 }
 
 impl PriorityLevel {
-    pub fn multiplier(&self) -> f64 {
+    /// Estimate for this priority level, read off the percentile that best
+    /// matches how aggressively the caller wants to land (median, p75 or p95).
+    /// `predict_fee` applies it to compute unit price percentiles.
+    pub fn base_fee(&self, percentiles: &FeePercentiles) -> u64 {
         match self {
-            PriorityLevel::Low => 0.8,
-            PriorityLevel::Medium => 1.0,
-            PriorityLevel::High => 1.5,
+            PriorityLevel::Low => percentiles.median,
+            PriorityLevel::Medium => percentiles.p75,
+            PriorityLevel::High => percentiles.p95,
         }
     }
 }
@@ -128,9 +373,66 @@ impl Default for PriorityLevel {
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Default)]
 pub struct FeePrediction {
     pub estimated_fee: u64,
+    /// Recommended compute unit price, in micro-lamports per CU, for
+    /// `ComputeBudgetInstruction::set_compute_unit_price`
+    pub recommended_compute_unit_price: u64,
     pub last_updated: i64,
     pub confidence: u8, // 0-100
     pub priority_level: PriorityLevel,
+    /// Distribution of observed `compute_unit_price` (micro-lamports per CU)
+    /// across the window, not the total fee
+    pub compute_unit_price_percentiles: FeePercentiles,
+}
+
+/// Account-keyed fee data, tracking observed prioritization fees per writable account
+#[account]
+pub struct AccountFeeData {
+    pub authority: Pubkey,
+    pub last_updated: i64,
+    pub accounts: Vec<AccountFeeRecord>,
+}
+
+/// Fees observed for transactions that locked a given writable account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AccountFeeRecord {
+    pub account: Pubkey,
+    pub fees: Vec<u64>,
+    pub compute_units_consumed: u64,
+    pub last_updated: i64,
+}
+
+/// Fee recommendation derived from the accounts a transaction writes to
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Default)]
+pub struct AccountFeePrediction {
+    pub recommended_fee: u64,
+    pub max_fee: u64,
+    pub min_fee: u64,
+}
+
+/// Windowed fee history, modeled on Ethereum's `eth_feeHistory`
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Default)]
+pub struct FeeHistory {
+    pub oldest_slot: u64,
+    pub base_fees: Vec<u64>,
+    pub rewards: Vec<Vec<u64>>,
+}
+
+/// Maximum distinct writable accounts tracked before the least-recently-updated is evicted
+pub const MAX_TRACKED_ACCOUNTS: usize = 64;
+/// Maximum fee samples retained per tracked account
+pub const MAX_FEES_PER_ACCOUNT: usize = 32;
+
+/// Order statistics (min/p25/median/p75/p90/p95/max) over a u64-valued field of the
+/// sample window, e.g. `compute_unit_price` or `fee` depending on the caller
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct FeePercentiles {
+    pub min: u64,
+    pub p25: u64,
+    pub median: u64,
+    pub p75: u64,
+    pub p90: u64,
+    pub p95: u64,
+    pub max: u64,
 }
 
 /// Initialize context
@@ -139,7 +441,9 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = payer,
-        space = 8 + 32 + 8 + (4 + 144 * std::mem::size_of::<FeeSample>()), // ~12KB
+        space = 8 + 32 + 8 + 8
+            + (4 + MAX_HISTORICAL_SAMPLES * std::mem::size_of::<FeeSample>())
+            + (4 + MAX_FEE_BINS * std::mem::size_of::<FeeBin>()), // ~7KB
         seeds = [b"fee_data", payer.key().as_ref()],
         bump
     )]
@@ -160,6 +464,17 @@ pub struct StoreFeeData<'info> {
     pub authority: Signer<'info>,
 }
 
+/// Set fee bins context
+#[derive(Accounts)]
+pub struct SetFeeBins<'info> {
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub fee_data: Account<'info, FeeData>,
+    pub authority: Signer<'info>,
+}
+
 /// Predict fee context
 #[derive(Accounts)]
 pub struct PredictFee<'info> {
@@ -167,30 +482,162 @@ pub struct PredictFee<'info> {
     pub fee_data: Account<'info, FeeData>,
 }
 
-/// Helper function to calculate average of a field
-fn calculate_average<F>(data: &[FeeSample], field: F) -> u64 
+/// Initialize account fee data context
+#[derive(Accounts)]
+pub struct InitializeAccountFeeData<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 8 + (4 + MAX_TRACKED_ACCOUNTS * (32 + 4 + MAX_FEES_PER_ACCOUNT * 8 + 8 + 8)),
+        seeds = [b"account_fee_data", payer.key().as_ref()],
+        bump
+    )]
+    pub account_fee_data: Account<'info, AccountFeeData>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Store account fee data context
+#[derive(Accounts)]
+pub struct StoreAccountFeeData<'info> {
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub account_fee_data: Account<'info, AccountFeeData>,
+    pub authority: Signer<'info>,
+}
+
+/// Predict fee for accounts context
+#[derive(Accounts)]
+pub struct PredictFeeForAccounts<'info> {
+    #[account()]
+    pub account_fee_data: Account<'info, AccountFeeData>,
+}
+
+/// Evict the tracked account that has gone the longest without an update, making
+/// room for a newly-observed writable account.
+fn evict_least_recently_updated(accounts: &mut Vec<AccountFeeRecord>) {
+    if let Some((oldest_idx, _)) = accounts
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, record)| record.last_updated)
+    {
+        accounts.remove(oldest_idx);
+    }
+}
+
+/// Evict the sample with the lowest combined score of recency-decay weight and
+/// occurrence count, mirroring the validator cost model's "old age AND low
+/// occurrence gets pushed out" eviction policy.
+fn evict_lowest_scored(data: &mut Vec<FeeSample>, lambda: f64, now: i64) {
+    // Normalize occurrence count onto the same [0, 1] scale as recency decay,
+    // otherwise occurrence (which climbs toward the window size) swamps decay
+    // and age stops factoring into eviction at all.
+    let max_occurrence = data
+        .iter()
+        .map(|s| s.occurrence_count)
+        .max()
+        .unwrap_or(1)
+        .max(1) as f64;
+
+    if let Some((lowest_idx, _)) = data.iter().enumerate().min_by(|(_, a), (_, b)| {
+        sample_score(a, lambda, now, max_occurrence).total_cmp(&sample_score(b, lambda, now, max_occurrence))
+    }) {
+        data.remove(lowest_idx);
+    }
+}
+
+/// Combined eviction score for a sample: recency-decay weight plus occurrence count,
+/// both normalized to [0, 1] so old age and low occurrence carry comparable weight
+fn sample_score(sample: &FeeSample, lambda: f64, now: i64, max_occurrence: f64) -> f64 {
+    recency_decay(sample.timestamp, now, lambda) + sample.occurrence_count as f64 / max_occurrence
+}
+
+/// Derive decay-weighted min/p25/median/p75/p90/p95/max order statistics over a field of
+/// the window's samples: each sample's weight decays with its age, so stale samples
+/// barely move the percentile boundaries. An empty window returns the defaults and a
+/// single-sample window returns that sample for every statistic.
+fn calculate_percentiles<F>(data: &[FeeSample], field: F, lambda: f64, now: i64) -> FeePercentiles
 where
     F: Fn(&FeeSample) -> u64,
 {
     if data.is_empty() {
-        return 0;
+        return FeePercentiles::default();
+    }
+
+    let mut weighted: Vec<(u64, f64)> = data
+        .iter()
+        .map(|s| (field(s), recency_decay(s.timestamp, now, lambda)))
+        .collect();
+    weighted.sort_unstable_by_key(|(value, _)| *value);
+
+    if weighted.len() == 1 {
+        let only = weighted[0].0;
+        return FeePercentiles {
+            min: only,
+            p25: only,
+            median: only,
+            p75: only,
+            p90: only,
+            p95: only,
+            max: only,
+        };
+    }
+
+    let total_weight: f64 = weighted.iter().map(|(_, weight)| weight).sum();
+    let weighted_percentile = |p: f64| -> u64 {
+        if total_weight <= 0.0 {
+            return weighted[weighted.len() / 2].0;
+        }
+        let target = total_weight * p;
+        let mut cumulative = 0.0;
+        for (value, weight) in &weighted {
+            cumulative += weight;
+            if cumulative >= target {
+                return *value;
+            }
+        }
+        weighted.last().unwrap().0
+    };
+
+    FeePercentiles {
+        min: weighted[0].0,
+        p25: weighted_percentile(0.25),
+        median: weighted_percentile(0.50),
+        p75: weighted_percentile(0.75),
+        p90: weighted_percentile(0.90),
+        p95: weighted_percentile(0.95),
+        max: weighted.last().unwrap().0,
     }
-    data.iter().map(field).sum::<u64>() / data.len() as u64
 }
 
-/// Calculate confidence score (0-100)
-fn calculate_confidence(data: &[FeeSample]) -> u8 {
+/// Calculate confidence score (0-100) from the decay-weighted interquartile
+/// spread of fees: a tight spread relative to the median means high confidence.
+fn calculate_confidence(data: &[FeeSample], lambda: f64, now: i64) -> u8 {
     if data.len() < 2 {
         return 0;
     }
-    
-    let avg = data.iter().map(|s| s.fee).sum::<u64>() as f64 / data.len() as f64;
-    let variance = data.iter()
-        .map(|s| (s.fee as f64 - avg).powi(2))
-        .sum::<f64>() / data.len() as f64;
-    
-    // Higher variance = lower confidence
-    (100.0 / (1.0 + variance.sqrt())).min(100.0) as u8
+
+    // Derive confidence from the interquartile spread relative to the median
+    // instead of raw standard deviation: absolute lamport variance collapses
+    // confidence to 0 whenever fees are large, even if the window is tight.
+    let percentiles = calculate_percentiles(data, |s| s.fee, lambda, now);
+    if percentiles.median == 0 {
+        return 0;
+    }
+
+    let spread = (percentiles.p75 - percentiles.p25) as f64 / percentiles.median as f64;
+
+    // Wider spread = lower confidence
+    (100.0 / (1.0 + spread)).min(100.0) as u8
+}
+
+/// Recency decay weight for a sample, `exp(-lambda * age)`
+fn recency_decay(timestamp: i64, now: i64, lambda: f64) -> f64 {
+    let age = (now - timestamp).max(0) as f64;
+    (-lambda * age).exp()
 }
 
 /// Error codes
@@ -200,4 +647,8 @@ pub enum ErrorCode {
     Unauthorized,
     #[msg("Insufficient historical data")]
     InsufficientData,
+    #[msg("Reward percentiles must be in 0..=100, strictly increasing, and number at most 100")]
+    InvalidPercentiles,
+    #[msg("Too many fee bins; at most MAX_FEE_BINS may be configured")]
+    TooManyFeeBins,
 }
\ No newline at end of file